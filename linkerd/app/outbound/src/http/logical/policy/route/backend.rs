@@ -5,11 +5,22 @@ use linkerd_http_route as http_route;
 use linkerd_proxy_client_policy as policy;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 
+mod body_filter;
+mod compression;
 mod count_reqs;
+mod grpc_status;
+mod hll;
 mod metrics;
+mod ratelimit;
+mod timeout;
 
 pub use self::count_reqs::RequestCount;
 pub use self::metrics::RouteBackendMetrics;
+use self::body_filter::{MaxBodySize, NewLimitBody};
+use self::compression::{Compress, CompressionPolicy, NewCompressResponse};
+use self::hll::{NewCountUniqueCallers, UniqueCallersParams};
+use self::ratelimit::{NewRateLimit, RateLimitParams, RateLimitPolicy, RateLimitedResponse};
+use self::timeout::{NewRequestTimeout, Timeout};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) struct Backend<T, F> {
@@ -17,6 +28,16 @@ pub(crate) struct Backend<T, F> {
     pub(crate) concrete: Concrete<T>,
     pub(crate) filters: Arc<[F]>,
     pub(crate) request_timeout: Option<std::time::Duration>,
+    /// Local rate-limit policy for this backend, if configured.
+    pub(crate) rate_limit: Option<RateLimitPolicy>,
+    /// Maximum accepted request body size for this backend, if configured.
+    pub(crate) max_body_size: Option<u64>,
+    /// Response-compression policy for this backend, if configured.
+    pub(crate) compression: Option<CompressionPolicy>,
+    /// How often this backend's unique-caller estimate is published and
+    /// reset, if configured; falls back to the registry's default window
+    /// when unset.
+    pub(crate) unique_callers_window: Option<std::time::Duration>,
 }
 
 pub(crate) type MatchedBackend<T, M, F> = super::Matched<M, Backend<T, F>>;
@@ -48,6 +69,10 @@ impl<T: Clone, F> Clone for Backend<T, F> {
             filters: self.filters.clone(),
             concrete: self.concrete.clone(),
             request_timeout: self.request_timeout,
+            rate_limit: self.rate_limit.clone(),
+            max_body_size: self.max_body_size,
+            compression: self.compression.clone(),
+            unique_callers_window: self.unique_callers_window,
         }
     }
 }
@@ -76,7 +101,17 @@ where
     F: Clone + Send + Sync + 'static,
     // Assert that filters can be applied.
     Self: filters::Apply,
+    // Assert that a response timeout can be computed for this backend kind.
+    Self: Timeout,
+    // Assert that a rate-limited response can be built for this backend kind.
+    Self: RateLimitedResponse,
+    // Assert that a request-body size limit can be read for this backend kind.
+    Self: MaxBodySize,
+    // Assert that a response-compression policy can be read for this backend kind.
+    Self: Compress,
     ExtractMetrics: svc::ExtractParam<RequestCount, Self>,
+    ExtractMetrics: svc::ExtractParam<RateLimitParams, Self>,
+    ExtractMetrics: svc::ExtractParam<UniqueCallersParams, Self>,
 {
     /// Builds a stack that applies per-route-backend policy filters over an
     /// inner [`Concrete`] stack.
@@ -118,10 +153,18 @@ where
                      }| concrete,
                 )
                 .push(filters::NewApplyFilters::<Self, _, _>::layer())
-                .push(http::NewTimeout::layer())
+                .push(NewLimitBody::layer())
+                .push(NewRateLimit::layer_via(ExtractMetrics {
+                    metrics: metrics.clone(),
+                }))
+                .push(NewRequestTimeout::layer())
+                .push(NewCompressResponse::layer())
                 .push(count_reqs::NewCountRequests::layer_via(ExtractMetrics {
                     metrics: metrics.clone(),
                 }))
+                .push(NewCountUniqueCallers::layer_via(ExtractMetrics {
+                    metrics: metrics.clone(),
+                }))
                 .push(svc::NewMapErr::layer_with(|t: &Self| {
                     let backend = t.params.concrete.backend_ref.clone();
                     move |source| {
@@ -137,12 +180,6 @@ where
     }
 }
 
-impl<T, M, F> svc::Param<http::ResponseTimeout> for MatchedBackend<T, M, F> {
-    fn param(&self) -> http::ResponseTimeout {
-        http::ResponseTimeout(self.params.request_timeout)
-    }
-}
-
 impl<T> filters::Apply for Http<T> {
     #[inline]
     fn apply<B>(&self, req: &mut ::http::Request<B>) -> Result<()> {
@@ -157,6 +194,134 @@ impl<T> filters::Apply for Grpc<T> {
     }
 }
 
+impl<T> Timeout for Http<T> {
+    fn response_timeout<B>(&self, _req: &::http::Request<B>) -> Option<std::time::Duration> {
+        self.params.request_timeout
+    }
+
+    fn timeout_response(&self) -> http::Response<http::BoxBody> {
+        http::Response::builder()
+            .status(http::StatusCode::GATEWAY_TIMEOUT)
+            .body(http::BoxBody::empty())
+            .expect("response must be valid")
+    }
+}
+
+impl<T> Timeout for Grpc<T> {
+    /// Honors the tighter of the route's configured timeout and the
+    /// client's `grpc-timeout` header, if either is present.
+    fn response_timeout<B>(&self, req: &::http::Request<B>) -> Option<std::time::Duration> {
+        let route_timeout = self.params.request_timeout;
+        let client_deadline = timeout::client_deadline(req);
+        match (route_timeout, client_deadline) {
+            (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn timeout_response(&self) -> http::Response<http::BoxBody> {
+        timeout::grpc_deadline_exceeded()
+    }
+}
+
+impl<T> RateLimitedResponse for Http<T> {
+    fn ratelimited_response(&self) -> http::Response<http::BoxBody> {
+        ratelimit::http_ratelimited_response()
+    }
+}
+
+impl<T> RateLimitedResponse for Grpc<T> {
+    fn ratelimited_response(&self) -> http::Response<http::BoxBody> {
+        ratelimit::grpc_ratelimited_response()
+    }
+}
+
+impl<T> Compress for Http<T> {
+    fn compression_policy(&self) -> Option<CompressionPolicy> {
+        self.params.compression.clone()
+    }
+}
+
+impl<T> Compress for Grpc<T> {
+    /// gRPC backends negotiate message compression via `grpc-encoding`
+    /// instead; this layer doesn't apply to them.
+    fn compression_policy(&self) -> Option<CompressionPolicy> {
+        None
+    }
+}
+
+impl<T> MaxBodySize for Http<T> {
+    fn max_body_size(&self) -> Option<u64> {
+        self.params.max_body_size
+    }
+
+    fn body_too_large_response(&self) -> http::Response<http::BoxBody> {
+        body_filter::http_body_too_large_response()
+    }
+}
+
+impl<T> MaxBodySize for Grpc<T> {
+    fn max_body_size(&self) -> Option<u64> {
+        self.params.max_body_size
+    }
+
+    fn body_too_large_response(&self) -> http::Response<http::BoxBody> {
+        body_filter::grpc_body_too_large_response()
+    }
+}
+
+impl<T> svc::ExtractParam<RateLimitParams, Http<T>> for ExtractMetrics {
+    fn extract_param(&self, params: &Http<T>) -> RateLimitParams {
+        RateLimitParams {
+            policy: params.params.rate_limit.clone(),
+            ratelimited: self.metrics.http_ratelimited_total(
+                params.params.concrete.parent_ref.clone(),
+                params.params.route_ref.clone(),
+                params.params.concrete.backend_ref.clone(),
+            ),
+        }
+    }
+}
+
+impl<T> svc::ExtractParam<RateLimitParams, Grpc<T>> for ExtractMetrics {
+    fn extract_param(&self, params: &Grpc<T>) -> RateLimitParams {
+        RateLimitParams {
+            policy: params.params.rate_limit.clone(),
+            ratelimited: self.metrics.grpc_ratelimited_total(
+                params.params.concrete.parent_ref.clone(),
+                params.params.route_ref.clone(),
+                params.params.concrete.backend_ref.clone(),
+            ),
+        }
+    }
+}
+
+impl<T> svc::ExtractParam<UniqueCallersParams, Http<T>> for ExtractMetrics {
+    fn extract_param(&self, params: &Http<T>) -> UniqueCallersParams {
+        UniqueCallersParams {
+            callers: self.metrics.http_unique_callers(
+                params.params.concrete.parent_ref.clone(),
+                params.params.route_ref.clone(),
+                params.params.concrete.backend_ref.clone(),
+                params.params.unique_callers_window,
+            ),
+        }
+    }
+}
+
+impl<T> svc::ExtractParam<UniqueCallersParams, Grpc<T>> for ExtractMetrics {
+    fn extract_param(&self, params: &Grpc<T>) -> UniqueCallersParams {
+        UniqueCallersParams {
+            callers: self.metrics.grpc_unique_callers(
+                params.params.concrete.parent_ref.clone(),
+                params.params.route_ref.clone(),
+                params.params.concrete.backend_ref.clone(),
+                params.params.unique_callers_window,
+            ),
+        }
+    }
+}
+
 impl<T> svc::ExtractParam<RequestCount, Http<T>> for ExtractMetrics {
     fn extract_param(&self, params: &Http<T>) -> RequestCount {
         RequestCount(self.metrics.http_requests_total(
@@ -0,0 +1,422 @@
+use linkerd_app_core::{proxy::http, svc, Error};
+use std::{
+    io::{self, Write},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The compression codecs this proxy knows how to negotiate. Which one is
+/// preferred when a client accepts more than one is set per route by the
+/// order of [`CompressionPolicy::codecs`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum Codec {
+    Zstd,
+    Br,
+    Gzip,
+}
+
+impl Codec {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Br => "br",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zstd" => Some(Self::Zstd),
+            "br" => Some(Self::Br),
+            "gzip" => Some(Self::Gzip),
+            _ => None,
+        }
+    }
+}
+
+/// Per-backend compression policy: the codecs a route is willing to use (in
+/// server-preference order) and the minimum response size worth
+/// compressing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CompressionPolicy {
+    pub(crate) codecs: Vec<Codec>,
+    pub(crate) min_size: u64,
+}
+
+/// Implemented by [`MatchedBackend`](super::super::MatchedBackend) targets
+/// to expose their [`CompressionPolicy`], if any. gRPC backends have their
+/// own message-level `grpc-encoding` compression and don't participate
+/// here.
+pub(crate) trait Compress {
+    fn compression_policy(&self) -> Option<CompressionPolicy>;
+}
+
+/// Picks the most-preferred codec in `codecs` (server-preference order)
+/// that's acceptable per the client's `accept-encoding` header, respecting
+/// `q=` weights and `identity`/`*`. Returns `None` if the client should
+/// receive an uncompressed response (no match, or `identity`/no header
+/// preferred over everything offered).
+///
+/// The client's `q=` weights only decide whether a codec is acceptable
+/// (`q > 0`) and whether it beats an explicit `identity`; they don't rank
+/// acceptable codecs against each other. `codecs` is already in the
+/// server's preference order, so this walks it in order and takes the
+/// first one the client will accept, whatever the client's relative
+/// weights.
+fn negotiate(accept_encoding: &str, codecs: &[Codec]) -> Option<Codec> {
+    // `None` until the client explicitly names `identity`: with no header
+    // preference stated, identity has no implicit weight to beat, so it
+    // never blocks an otherwise-acceptable codec.
+    let mut identity_q: Option<f32> = None;
+    let mut star_q: Option<f32> = None;
+    let mut named_q: Vec<(&str, f32)> = Vec::new();
+
+    for part in accept_encoding.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let mut it = part.split(';');
+        let name = it.next().unwrap_or("").trim();
+        let q = it
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .and_then(|v| v.parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        if name.eq_ignore_ascii_case("identity") {
+            identity_q = Some(q);
+        } else if name == "*" {
+            star_q = Some(q);
+        } else {
+            named_q.push((name, q));
+        }
+    }
+
+    let accepted_q = |codec: &Codec| -> Option<f32> {
+        named_q
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(codec.name()))
+            .map(|(_, q)| *q)
+            .or(star_q)
+    };
+
+    codecs.iter().find_map(|codec| {
+        let q = accepted_q(codec)?;
+        // An explicit, strictly higher-weighted `identity` preference wins
+        // over a compressed match; on equal weights the codec wins.
+        let beats_identity = identity_q.map(|iq| q >= iq).unwrap_or(true);
+        (q > 0.0 && beats_identity).then_some(*codec)
+    })
+}
+
+/// Content types that are already compressed, or otherwise not worth
+/// re-compressing.
+fn is_compressible(content_type: Option<&str>) -> bool {
+    match content_type {
+        None => true,
+        Some(ct) => {
+            let ct = ct.split(';').next().unwrap_or(ct).trim();
+            !matches!(
+                ct,
+                "application/grpc"
+                    | "application/zip"
+                    | "application/gzip"
+                    | "application/x-gzip"
+                    | "image/jpeg"
+                    | "image/png"
+                    | "image/webp"
+                    | "video/mp4"
+                    | "video/webm"
+            )
+        }
+    }
+}
+
+/// A [`svc::NewService`] that negotiates and applies response compression
+/// according to a target's [`Compress::compression_policy`].
+#[derive(Clone)]
+pub(crate) struct NewCompressResponse<N> {
+    inner: N,
+}
+
+impl<N> NewCompressResponse<N> {
+    pub(crate) fn layer() -> impl svc::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewCompressResponse<N>
+where
+    T: Compress + Clone + Send + Sync + 'static,
+    N: svc::NewService<T>,
+{
+    type Service = CompressResponse<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let policy = target.compression_policy();
+        let inner = self.inner.new_service(target);
+        CompressResponse { policy, inner }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct CompressResponse<S> {
+    policy: Option<CompressionPolicy>,
+    inner: S,
+}
+
+impl<S, B> svc::Service<http::Request<B>> for CompressResponse<S>
+where
+    S: svc::Service<http::Request<B>, Response = http::Response<http::BoxBody>, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<http::BoxBody>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let Some(policy) = self.policy.clone() else {
+            return Box::pin(self.inner.call(req));
+        };
+
+        let codec = req
+            .headers()
+            .get(http::header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|accept| negotiate(accept, &policy.codecs));
+
+        let call = self.inner.call(req);
+        Box::pin(async move {
+            let mut rsp = call.await?;
+            let Some(codec) = codec else {
+                return Ok(rsp);
+            };
+            if rsp.headers().contains_key(http::header::CONTENT_ENCODING) {
+                return Ok(rsp);
+            }
+            let content_type = rsp
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned);
+            if !is_compressible(content_type.as_deref()) {
+                return Ok(rsp);
+            }
+            if let Some(len) = rsp
+                .headers()
+                .get(http::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+            {
+                if len < policy.min_size {
+                    return Ok(rsp);
+                }
+            }
+
+            rsp.headers_mut().insert(
+                http::header::CONTENT_ENCODING,
+                http::HeaderValue::from_static(codec.name()),
+            );
+            rsp.headers_mut()
+                .insert(http::header::VARY, http::HeaderValue::from_static("accept-encoding"));
+            rsp.headers_mut().remove(http::header::CONTENT_LENGTH);
+
+            let rsp = rsp.map(|body| http::BoxBody::new(CompressedBody::new(body, codec)));
+            Ok(rsp)
+        })
+    }
+}
+
+enum Encoder {
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+    Br(brotli::CompressorWriter<Vec<u8>>),
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(codec: Codec) -> io::Result<Self> {
+        Ok(match codec {
+            Codec::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(Vec::new(), 0)?),
+            Codec::Br => Self::Br(brotli::CompressorWriter::new(Vec::new(), 8 * 1024, 5, 22)),
+            Codec::Gzip => {
+                Self::Gzip(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast()))
+            }
+        })
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Zstd(e) => e.write_all(data),
+            Self::Br(e) => e.write_all(data),
+            Self::Gzip(e) => e.write_all(data),
+        }
+    }
+
+    fn take_output(&mut self) -> Vec<u8> {
+        let buf = match self {
+            Self::Zstd(e) => e.get_mut(),
+            Self::Br(e) => e.get_mut(),
+            Self::Gzip(e) => e.get_mut(),
+        };
+        std::mem::take(buf)
+    }
+
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Zstd(e) => e.finish(),
+            // `into_inner` finishes the brotli stream before returning the
+            // buffer; a plain `flush` would leave it unterminated.
+            Self::Br(e) => Ok(e.into_inner()),
+            Self::Gzip(e) => e.finish(),
+        }
+    }
+}
+
+/// Wraps a response body, compressing each data frame as it streams
+/// through so the whole response is never buffered in memory.
+struct CompressedBody {
+    inner: http::BoxBody,
+    encoder: Option<Encoder>,
+}
+
+impl CompressedBody {
+    fn new(inner: http::BoxBody, codec: Codec) -> Self {
+        let encoder = Encoder::new(codec).ok();
+        Self { inner, encoder }
+    }
+}
+
+impl http_body::Body for CompressedBody {
+    type Data = bytes::Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let Some(encoder) = this.encoder.as_mut() else {
+            // Construction failed; fall back to passing the body through
+            // uncompressed rather than failing the response outright.
+            return Pin::new(&mut this.inner)
+                .poll_frame(cx)
+                .map_err(Into::into);
+        };
+
+        loop {
+            match Pin::new(&mut this.inner).poll_frame(cx) {
+                Poll::Ready(Some(Ok(frame))) => match frame.into_data() {
+                    Ok(data) => {
+                        encoder.write_data(&data)?;
+                        let out = encoder.take_output();
+                        if out.is_empty() {
+                            continue;
+                        }
+                        return Poll::Ready(Some(Ok(http_body::Frame::data(out.into()))));
+                    }
+                    Err(frame) => {
+                        // Trailers: flush and finish the encoder first.
+                        let tail = this.encoder.take().unwrap().finish()?;
+                        if !tail.is_empty() {
+                            // Stash the trailers frame for the next poll by
+                            // re-wrapping the inner body as already drained;
+                            // emit the tail now and the trailers next call.
+                            this.inner = http::BoxBody::new(PendingTrailers(Some(
+                                frame.into_trailers().ok().unwrap_or_default(),
+                            )));
+                            return Poll::Ready(Some(Ok(http_body::Frame::data(tail.into()))));
+                        }
+                        return Poll::Ready(frame.into_trailers().ok().map(|t| {
+                            Ok(http_body::Frame::trailers(t))
+                        }));
+                    }
+                },
+                Poll::Ready(None) => {
+                    let tail = match this.encoder.take() {
+                        Some(e) => e.finish()?,
+                        None => Vec::new(),
+                    };
+                    if tail.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    // `this.inner` already returned `None` once; polling it
+                    // again is unsound to rely on, so swap in an empty body
+                    // for the next poll the same way the trailers arm above
+                    // stashes a `PendingTrailers` stand-in.
+                    this.inner = http::BoxBody::empty();
+                    return Poll::Ready(Some(Ok(http_body::Frame::data(tail.into()))));
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.encoder.is_none() && self.inner.is_end_stream()
+    }
+}
+
+/// Emits a single buffered trailers frame, used by [`CompressedBody`] to
+/// flush the encoder's tail bytes as one final data frame ahead of
+/// trailers that arrived in the same poll.
+struct PendingTrailers(Option<http::HeaderMap>);
+
+impl http_body::Body for PendingTrailers {
+    type Data = bytes::Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(self.get_mut().0.take().map(|t| Ok(http_body::Frame::trailers(t))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODECS: &[Codec] = &[Codec::Zstd, Codec::Br, Codec::Gzip];
+
+    #[test]
+    fn negotiate_picks_server_preference_over_client_weights() {
+        // The client weights `gzip` higher, but the server prefers `br`
+        // and both are acceptable: the server's preference wins.
+        assert_eq!(negotiate("gzip;q=1.0, br;q=0.5", CODECS), Some(Codec::Br));
+    }
+
+    #[test]
+    fn negotiate_skips_unacceptable_server_preference() {
+        assert_eq!(negotiate("gzip", CODECS), Some(Codec::Gzip));
+    }
+
+    #[test]
+    fn negotiate_respects_q_zero_rejection() {
+        assert_eq!(negotiate("zstd;q=0, br;q=0, gzip;q=0", CODECS), None);
+    }
+
+    #[test]
+    fn negotiate_star_falls_back_to_first_configured() {
+        assert_eq!(negotiate("*", CODECS), Some(Codec::Zstd));
+    }
+
+    #[test]
+    fn negotiate_explicit_identity_preference_wins() {
+        assert_eq!(negotiate("identity;q=1.0, gzip;q=0.5", CODECS), None);
+    }
+
+    #[test]
+    fn negotiate_no_header_match_returns_none() {
+        assert_eq!(negotiate("br", &[Codec::Zstd]), None);
+    }
+}
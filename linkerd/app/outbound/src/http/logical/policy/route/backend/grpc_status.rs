@@ -0,0 +1,66 @@
+use linkerd_app_core::proxy::http;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Builds a trailers-only gRPC response carrying the given `grpc-status`
+/// code, e.g. in lieu of running the request when a deadline has elapsed or
+/// a local policy has rejected it.
+pub(crate) fn trailers_only(status: u8, message: &'static str) -> http::Response<http::BoxBody> {
+    let mut rsp = http::Response::builder()
+        .status(http::StatusCode::OK)
+        .header("content-type", "application/grpc")
+        .body(http::BoxBody::new(StatusBody {
+            status,
+            message,
+            emitted: false,
+        }))
+        .expect("response must be valid");
+    rsp.headers_mut().remove("content-length");
+    rsp
+}
+
+/// Builds the `grpc-status`/`grpc-message` trailer pair emitted by
+/// [`StatusBody`].
+fn trailers(status: u8, message: &'static str) -> http::HeaderMap {
+    let mut trailers = http::HeaderMap::with_capacity(2);
+    trailers.insert(
+        "grpc-status",
+        http::HeaderValue::from_str(&status.to_string()).expect("status must be valid"),
+    );
+    trailers.insert("grpc-message", http::HeaderValue::from_static(message));
+    trailers
+}
+
+/// A body with no data frames that completes with `grpc-status`/
+/// `grpc-message` trailers.
+struct StatusBody {
+    status: u8,
+    message: &'static str,
+    emitted: bool,
+}
+
+impl http_body::Body for StatusBody {
+    type Data = bytes::Bytes;
+    type Error = linkerd_app_core::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        if this.emitted {
+            return Poll::Ready(None);
+        }
+        this.emitted = true;
+        Poll::Ready(Some(Ok(http_body::Frame::trailers(trailers(
+            this.status,
+            this.message,
+        )))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.emitted
+    }
+}
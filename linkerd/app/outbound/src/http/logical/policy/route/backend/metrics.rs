@@ -0,0 +1,140 @@
+use super::hll::UniqueCallers;
+use crate::{BackendRef, ParentRef, RouteRef};
+use linkerd_app_core::metrics::{Counter, Gauge};
+use std::{
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type Key = (ParentRef, RouteRef, BackendRef);
+
+/// How long an `UniqueCallers` HyperLogLog window covers before its
+/// estimate is published to the gauge and reset.
+const UNIQUE_CALLERS_WINDOW: Duration = Duration::from_secs(60);
+
+/// Per-`(parent, route, backend)` metrics shared by the `Http<T>`/`Grpc<T>`
+/// route-backend stacks. Counters and gauges are created lazily and cached
+/// by key so that repeat lookups for the same backend return the same
+/// handle instead of re-registering it with the process's metrics
+/// registry.
+#[derive(Clone, Debug, Default)]
+pub struct RouteBackendMetrics {
+    http_requests: Registry<Counter>,
+    grpc_requests: Registry<Counter>,
+    http_ratelimited: Registry<Counter>,
+    grpc_ratelimited: Registry<Counter>,
+    http_unique_callers: Registry<UniqueCallers>,
+    grpc_unique_callers: Registry<UniqueCallers>,
+}
+
+#[derive(Debug)]
+struct Registry<V>(Arc<Mutex<std::collections::HashMap<Key, V>>>);
+
+impl<V> Clone for Registry<V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V> Default for Registry<V> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<V: Clone + Default> Registry<V> {
+    fn get_or_default(&self, key: Key) -> V {
+        self.0.lock().expect("lock must not be poisoned").entry(key).or_default().clone()
+    }
+}
+
+impl Registry<UniqueCallers> {
+    /// Looks up the `UniqueCallers` handle for `key`, spawning one with the
+    /// given `window` (falling back to `UNIQUE_CALLERS_WINDOW` when `None`)
+    /// if this is the first lookup. The window only takes effect on that
+    /// first lookup, since later ones just clone the already-spawned handle.
+    fn get_or_spawn(&self, key: Key, window: Option<Duration>) -> UniqueCallers {
+        self.0
+            .lock()
+            .expect("lock must not be poisoned")
+            .entry(key)
+            .or_insert_with(|| {
+                UniqueCallers::spawn(Gauge::default(), window.unwrap_or(UNIQUE_CALLERS_WINDOW))
+            })
+            .clone()
+    }
+}
+
+// === impl RouteBackendMetrics ===
+
+impl RouteBackendMetrics {
+    pub(crate) fn http_requests_total(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+    ) -> Counter {
+        self.http_requests.get_or_default((parent_ref, route_ref, backend_ref))
+    }
+
+    pub(crate) fn grpc_requests_total(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+    ) -> Counter {
+        self.grpc_requests.get_or_default((parent_ref, route_ref, backend_ref))
+    }
+
+    pub(crate) fn http_ratelimited_total(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+    ) -> Counter {
+        self.http_ratelimited.get_or_default((parent_ref, route_ref, backend_ref))
+    }
+
+    pub(crate) fn grpc_ratelimited_total(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+    ) -> Counter {
+        self.grpc_ratelimited.get_or_default((parent_ref, route_ref, backend_ref))
+    }
+
+    pub(crate) fn http_unique_callers(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+        window: Option<Duration>,
+    ) -> UniqueCallers {
+        self.http_unique_callers
+            .get_or_spawn((parent_ref, route_ref, backend_ref), window)
+    }
+
+    pub(crate) fn grpc_unique_callers(
+        &self,
+        parent_ref: ParentRef,
+        route_ref: RouteRef,
+        backend_ref: BackendRef,
+        window: Option<Duration>,
+    ) -> UniqueCallers {
+        self.grpc_unique_callers
+            .get_or_spawn((parent_ref, route_ref, backend_ref), window)
+    }
+}
+
+// Keys must be hashable; this is a compile-time assertion that states, in
+// one place, the bounds the ref types this module is keyed by must satisfy
+// (the `HashMap` lookups above also require them).
+fn _assert_key_hashable()
+where
+    ParentRef: Hash + Eq + Clone,
+    RouteRef: Hash + Eq + Clone,
+    BackendRef: Hash + Eq + Clone,
+{
+}
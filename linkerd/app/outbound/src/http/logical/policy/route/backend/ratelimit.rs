@@ -0,0 +1,282 @@
+use super::grpc_status;
+use linkerd_app_core::{proxy::http, svc, Error};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+/// A token bucket, refilled continuously at `rate` tokens/sec up to a
+/// `burst` capacity. Tokens are tracked in milli-token units so that
+/// sub-second refills don't round away to zero.
+struct TokenBucket {
+    capacity: u64,
+    rate_per_sec: u64,
+    tokens: AtomicU64,
+    last_refill: Mutex<Instant>,
+}
+
+const MILLI: u64 = 1_000;
+
+impl TokenBucket {
+    fn new(burst: u32, rate: u32) -> Self {
+        let capacity = u64::from(burst) * MILLI;
+        Self {
+            capacity,
+            rate_per_sec: u64::from(rate) * MILLI,
+            tokens: AtomicU64::new(capacity),
+            last_refill: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Refills the bucket based on elapsed time, then attempts to consume a
+    /// single token. Returns `true` if a token was available.
+    fn try_acquire(&self) -> bool {
+        self.refill();
+
+        let mut current = self.tokens.load(Ordering::Acquire);
+        loop {
+            if current < MILLI {
+                return false;
+            }
+            let next = current - MILLI;
+            match self.tokens.compare_exchange_weak(
+                current,
+                next,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    fn refill(&self) {
+        let mut last = self.last_refill.lock().expect("lock must not be poisoned");
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(*last);
+        let added = (elapsed.as_secs_f64() * self.rate_per_sec as f64) as u64;
+        if added == 0 {
+            return;
+        }
+        *last = now;
+        drop(last);
+
+        let mut current = self.tokens.load(Ordering::Acquire);
+        loop {
+            let next = current.saturating_add(added).min(self.capacity);
+            match self.tokens.compare_exchange_weak(
+                current,
+                next,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return,
+                Err(observed) => current = observed,
+            }
+        }
+    }
+}
+
+/// A backend's local rate-limit policy: a token-bucket `burst`/`rate`, and
+/// an optional request header to key buckets per-caller (e.g. `host` or a
+/// client-id header). The key is looked up in the request's header map, so
+/// pseudo-headers such as `:authority` can't be used as the key. When `key`
+/// is unset, the backend shares a single bucket across all callers.
+///
+/// Carried directly on [`super::Backend`] (like `request_timeout`) rather
+/// than as a policy `Filter` variant, since it's backend-wide
+/// configuration rather than a per-request rewrite.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct RateLimitPolicy {
+    pub(crate) burst: u32,
+    pub(crate) rate: u32,
+    pub(crate) key: Option<http::HeaderName>,
+}
+
+/// The rate-limit policy for a target, paired with the counter incremented
+/// when the limiter rejects a request. Mirrors [`super::RequestCount`] /
+/// `ExtractMetrics`'s extraction of per-target metrics handles.
+#[derive(Clone)]
+pub(crate) struct RateLimitParams {
+    pub(crate) policy: Option<RateLimitPolicy>,
+    pub(crate) ratelimited: linkerd_app_core::metrics::Counter,
+}
+
+/// Builds the response returned when a request is rejected by the rate
+/// limiter.
+pub(crate) trait RateLimitedResponse {
+    fn ratelimited_response(&self) -> http::Response<http::BoxBody>;
+}
+
+pub(crate) fn http_ratelimited_response() -> http::Response<http::BoxBody> {
+    http::Response::builder()
+        .status(http::StatusCode::TOO_MANY_REQUESTS)
+        .body(http::BoxBody::empty())
+        .expect("response must be valid")
+}
+
+pub(crate) fn grpc_ratelimited_response() -> http::Response<http::BoxBody> {
+    grpc_status::trailers_only(8, "resource exhausted")
+}
+
+/// Upper bound on the number of distinct per-caller buckets a keyed
+/// [`Buckets::Keyed`] will track at once. The key is attacker-controlled
+/// (an arbitrary request header), so without a cap a caller could grow the
+/// map without bound; once at capacity, callers with a not-yet-seen key
+/// share a single fallback bucket instead of getting one of their own.
+const MAX_KEYED_BUCKETS: usize = 10_000;
+
+/// Buckets a backend's rate limit, either as a single bucket shared by all
+/// callers, or keyed per-caller by [`RateLimitPolicy::key`].
+enum Buckets {
+    Shared(TokenBucket),
+    Keyed {
+        rate: u32,
+        burst: u32,
+        key: http::HeaderName,
+        buckets: Mutex<HashMap<Box<str>, Arc<TokenBucket>>>,
+        /// Shared by callers once `buckets` is at [`MAX_KEYED_BUCKETS`].
+        overflow: TokenBucket,
+    },
+}
+
+impl Buckets {
+    fn new(policy: &RateLimitPolicy) -> Self {
+        match &policy.key {
+            None => Self::Shared(TokenBucket::new(policy.burst, policy.rate)),
+            Some(key) => Self::Keyed {
+                rate: policy.rate,
+                burst: policy.burst,
+                key: key.clone(),
+                buckets: Mutex::new(HashMap::new()),
+                overflow: TokenBucket::new(policy.burst, policy.rate),
+            },
+        }
+    }
+
+    fn try_acquire<B>(&self, req: &::http::Request<B>) -> bool {
+        match self {
+            Self::Shared(bucket) => bucket.try_acquire(),
+            Self::Keyed {
+                rate,
+                burst,
+                key,
+                buckets,
+                overflow,
+            } => {
+                let descriptor = req
+                    .headers()
+                    .get(key)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("");
+                let mut buckets = buckets.lock().expect("lock must not be poisoned");
+                if let Some(bucket) = buckets.get(descriptor) {
+                    return bucket.try_acquire();
+                }
+                if buckets.len() >= MAX_KEYED_BUCKETS {
+                    return overflow.try_acquire();
+                }
+                let bucket = buckets
+                    .entry(descriptor.into())
+                    .or_insert_with(|| Arc::new(TokenBucket::new(*burst, *rate)));
+                bucket.try_acquire()
+            }
+        }
+    }
+}
+
+/// A [`svc::NewService`] that enforces a per-backend [`RateLimitPolicy`],
+/// rejecting requests with [`RateLimitedResponse::ratelimited_response`]
+/// once the backend's token bucket is exhausted.
+#[derive(Clone)]
+pub(crate) struct NewRateLimit<X, N> {
+    extract: X,
+    inner: N,
+}
+
+impl<X: Clone, N> NewRateLimit<X, N> {
+    pub(crate) fn layer_via(extract: X) -> impl svc::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            extract: extract.clone(),
+            inner,
+        })
+    }
+}
+
+impl<T, X, N> svc::NewService<T> for NewRateLimit<X, N>
+where
+    T: RateLimitedResponse + Clone + Send + Sync + 'static,
+    X: svc::ExtractParam<RateLimitParams, T>,
+    N: svc::NewService<T>,
+{
+    type Service = RateLimit<T, N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let RateLimitParams {
+            policy,
+            ratelimited,
+        } = self.extract.extract_param(&target);
+        let buckets = policy.as_ref().map(Buckets::new).map(Arc::new);
+        let inner = self.inner.new_service(target.clone());
+        RateLimit {
+            target,
+            buckets,
+            ratelimited,
+            inner,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct RateLimit<T, S> {
+    target: T,
+    buckets: Option<Arc<Buckets>>,
+    ratelimited: linkerd_app_core::metrics::Counter,
+    inner: S,
+}
+
+impl<T, S> fmt::Debug for RateLimit<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("limited", &self.buckets.is_some())
+            .finish()
+    }
+}
+
+impl<T, S, B> svc::Service<::http::Request<B>> for RateLimit<T, S>
+where
+    T: RateLimitedResponse + Clone,
+    S: svc::Service<::http::Request<B>, Response = http::Response<http::BoxBody>, Error = Error>,
+{
+    type Response = http::Response<http::BoxBody>;
+    type Error = Error;
+    type Future = futures::future::Either<
+        S::Future,
+        futures::future::Ready<Result<Self::Response, Self::Error>>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ::http::Request<B>) -> Self::Future {
+        if let Some(buckets) = &self.buckets {
+            if !buckets.try_acquire(&req) {
+                self.ratelimited.incr();
+                return futures::future::Either::Right(futures::future::ready(Ok(
+                    self.target.ratelimited_response(),
+                )));
+            }
+        }
+        futures::future::Either::Left(self.inner.call(req))
+    }
+}
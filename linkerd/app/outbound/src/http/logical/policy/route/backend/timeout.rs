@@ -0,0 +1,177 @@
+use super::grpc_status;
+use linkerd_app_core::{proxy::http, svc, Error};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tracing::warn;
+
+/// Implemented by [`MatchedBackend`](super::super::MatchedBackend) targets
+/// to determine the response timeout that applies to a given request, and
+/// the response to synthesize if that timeout elapses.
+///
+/// `Http<T>` honors only the route's configured timeout; `Grpc<T>` also
+/// honors a client-supplied `grpc-timeout` header, and returns a
+/// `grpc-status: 4` (DEADLINE_EXCEEDED) response rather than an HTTP
+/// timeout.
+pub(crate) trait Timeout {
+    fn response_timeout<B>(&self, req: &::http::Request<B>) -> Option<Duration>;
+
+    fn timeout_response(&self) -> http::Response<http::BoxBody>;
+}
+
+/// Header carrying a client's gRPC deadline, e.g. `10S`, `500m`.
+pub(crate) const GRPC_TIMEOUT: &str = "grpc-timeout";
+
+/// Parses a `grpc-timeout` header value per the gRPC over HTTP/2 spec: up to
+/// 8 ASCII digits followed by a single unit character.
+pub(crate) fn parse_grpc_timeout(value: &::http::HeaderValue) -> Option<Duration> {
+    let s = value.to_str().ok()?;
+    if s.is_empty() || s.len() > 9 {
+        return None;
+    }
+    let (digits, unit) = s.split_at(s.len() - 1);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n: u64 = digits.parse().ok()?;
+    let dur = match unit {
+        "H" => Duration::from_secs(n.saturating_mul(60 * 60)),
+        "M" => Duration::from_secs(n.saturating_mul(60)),
+        "S" => Duration::from_secs(n),
+        "m" => Duration::from_millis(n),
+        "u" => Duration::from_micros(n),
+        "n" => Duration::from_nanos(n),
+        _ => return None,
+    };
+    Some(dur)
+}
+
+/// Reads a client-supplied `grpc-timeout` deadline from `req`, returning
+/// `None` (and logging a warning) if the header is present but malformed.
+pub(crate) fn client_deadline<B>(req: &::http::Request<B>) -> Option<Duration> {
+    let value = req.headers().get(GRPC_TIMEOUT)?;
+    let parsed = parse_grpc_timeout(value);
+    if parsed.is_none() {
+        warn!(?value, "Ignoring malformed grpc-timeout header");
+    }
+    parsed
+}
+
+pub(crate) fn grpc_deadline_exceeded() -> http::Response<http::BoxBody> {
+    grpc_status::trailers_only(4, "deadline exceeded")
+}
+
+/// A [`svc::NewService`] that enforces the [`Timeout`] of its target,
+/// synthesizing a response via [`Timeout::timeout_response`] when it
+/// elapses instead of propagating a raw timeout error.
+#[derive(Clone, Debug)]
+pub(crate) struct NewRequestTimeout<N> {
+    inner: N,
+}
+
+impl<N> NewRequestTimeout<N> {
+    pub(crate) fn layer() -> impl svc::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewRequestTimeout<N>
+where
+    T: Timeout + Clone + Send + Sync + 'static,
+    N: svc::NewService<T>,
+{
+    type Service = RequestTimeout<T, N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let inner = self.inner.new_service(target.clone());
+        RequestTimeout { target, inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RequestTimeout<T, S> {
+    target: T,
+    inner: S,
+}
+
+impl<T, S> svc::Service<http::Request<http::BoxBody>> for RequestTimeout<T, S>
+where
+    T: Timeout + Clone + Send + Sync + 'static,
+    S: svc::Service<http::Request<http::BoxBody>, Response = http::Response<http::BoxBody>, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<http::BoxBody>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<http::BoxBody>) -> Self::Future {
+        let timeout = self.target.response_timeout(&req);
+        let call = self.inner.call(req);
+        let target = self.target.clone();
+        Box::pin(async move {
+            let Some(timeout) = timeout else {
+                return call.await;
+            };
+            match tokio::time::timeout(timeout, call).await {
+                Ok(res) => res,
+                Err(_) => Ok(target.timeout_response()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Option<Duration> {
+        parse_grpc_timeout(&::http::HeaderValue::from_str(value).expect("must be a valid header"))
+    }
+
+    #[test]
+    fn parses_each_unit() {
+        assert_eq!(parse("1H"), Some(Duration::from_secs(60 * 60)));
+        assert_eq!(parse("2M"), Some(Duration::from_secs(2 * 60)));
+        assert_eq!(parse("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse("4m"), Some(Duration::from_millis(4)));
+        assert_eq!(parse("5u"), Some(Duration::from_micros(5)));
+        assert_eq!(parse("6n"), Some(Duration::from_nanos(6)));
+    }
+
+    #[test]
+    fn accepts_eight_digits() {
+        assert_eq!(parse("12345678S"), Some(Duration::from_secs(12_345_678)));
+    }
+
+    #[test]
+    fn rejects_more_than_eight_digits() {
+        assert_eq!(parse("123456789S"), None);
+    }
+
+    #[test]
+    fn rejects_missing_unit() {
+        assert_eq!(parse("10"), None);
+    }
+
+    #[test]
+    fn rejects_unknown_unit() {
+        assert_eq!(parse("10X"), None);
+    }
+
+    #[test]
+    fn rejects_non_digit_magnitude() {
+        assert_eq!(parse("1a2S"), None);
+    }
+
+    #[test]
+    fn rejects_empty() {
+        assert_eq!(parse(""), None);
+    }
+}